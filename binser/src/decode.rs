@@ -1,36 +1,137 @@
 use super::error::Error;
+use std::io::Read;
 use std::mem;
 
+/// Upper bound on the size of an intermediate buffer filled from a stream.
+///
+/// A corrupt length field must not be able to trigger an unbounded allocation, so
+/// `StreamReader::read_vec` rejects any length larger than this with `Error::LengthLimitExceeded`.
+pub const MAX_BUF_SIZE: usize = 8 * 1024 * 1024;
+
+/// Default ceiling on the number of elements a length-prefixed collection may declare.
+///
+/// Tunable per-`Reader` via `Reader::with_max_length`.
+pub const MAX_ARRAY_LENGTH: usize = 64 * 1024;
+
+/// Byte order used when decoding multi-byte numbers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
 /// A struct representing a slice from which values implementing `Decode` can be read.
 pub struct Reader<'a> {
     data: &'a [u8],
     offset: usize,
+    endian: Endian,
+    max_length: usize,
 }
 
 impl<'a> Reader<'a> {
-    /// Constructs a new `Reader` with the provided data slice.
+    /// Constructs a new `Reader` with the provided data slice, defaulting to `new_le`.
     pub fn new(data: &'a [u8]) -> Reader<'a> {
-        Reader { data, offset: 0 }
+        Reader::new_le(data)
     }
 
-    /// Reads bytes to a buffer.
-    pub fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
-        if self.data.len() - self.offset < buffer.len() {
-            return Err(Error::Overflow);
+    /// Constructs a new little-endian `Reader` with the provided data slice.
+    pub fn new_le(data: &'a [u8]) -> Reader<'a> {
+        Reader {
+            data,
+            offset: 0,
+            endian: Endian::Little,
+            max_length: MAX_ARRAY_LENGTH,
         }
+    }
 
-        buffer.copy_from_slice(&self.data[self.offset..self.offset + buffer.len()]);
-        self.offset += buffer.len();
+    /// Constructs a new big-endian `Reader` with the provided data slice.
+    pub fn new_be(data: &'a [u8]) -> Reader<'a> {
+        Reader {
+            data,
+            offset: 0,
+            endian: Endian::Big,
+            max_length: MAX_ARRAY_LENGTH,
+        }
+    }
+
+    /// Sets the maximum number of elements a length-prefixed collection may declare.
+    pub fn with_max_length(mut self, limit: usize) -> Reader<'a> {
+        self.max_length = limit;
+        self
+    }
+
+    /// Returns a subslice of `len` bytes borrowed from the underlying buffer and advances the offset.
+    ///
+    /// Unlike `read_bytes`, no copying takes place; the returned slice is tied to the buffer lifetime.
+    pub fn read_slice(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let slice = self.read_slice_at(self.offset, len)?;
+        self.offset += len;
+
+        Ok(slice)
+    }
+
+    /// Returns a subslice of `len` bytes starting at `offset` without moving the cursor.
+    pub fn read_slice_at(&self, offset: usize, len: usize) -> Result<&'a [u8], Error> {
+        let remaining = self.data.len().saturating_sub(offset);
+        if offset > self.data.len() || remaining < len {
+            return Err(Error::UnexpectedEof {
+                offset,
+                needed: len,
+                remaining,
+            });
+        }
+
+        Ok(&self.data[offset..offset + len])
+    }
+
+    /// Reads a value implementing `DecodeRef` borrowing directly from the underlying buffer.
+    pub fn read_ref<T>(&mut self) -> Result<T, Error>
+    where
+        T: DecodeRef<'a>,
+    {
+        T::decode_ref(self)
+    }
+
+    /// Returns the current read offset.
+    pub fn index(&self) -> usize {
+        self.offset
+    }
+
+    /// Moves the cursor to an absolute offset within the buffer.
+    pub fn seek(&mut self, offset: usize) -> Result<(), Error> {
+        if offset > self.data.len() {
+            return Err(Error::UnexpectedEof {
+                offset,
+                needed: 0,
+                remaining: self.data.len().saturating_sub(offset),
+            });
+        }
+
+        self.offset = offset;
 
         Ok(())
     }
 
-    /// Reads a value implementing `Decode` from the data slice and advances its offset.
-    pub fn read<T>(&mut self) -> Result<T, Error>
+    /// Follows a back-reference: decodes a value at an absolute offset using a fresh sub-reader,
+    /// without disturbing `self`.
+    ///
+    /// The offset must point strictly before the current position, which guards against pointer
+    /// loops in self-referential formats; otherwise `Error::PointerOverlap` is returned.
+    pub fn read_from<T>(&self, offset: usize) -> Result<T, Error>
     where
         T: Decode,
     {
-        T::decode(self)
+        if offset >= self.offset {
+            return Err(Error::PointerOverlap);
+        }
+
+        Reader {
+            data: self.data,
+            offset,
+            endian: self.endian,
+            max_length: self.max_length,
+        }
+        .read()
     }
 
     /// Reads a value implementing `Decode` from the data slice at a specified offset.
@@ -39,20 +140,156 @@ impl<'a> Reader<'a> {
         T: Decode,
     {
         if offset >= self.data.len() {
-            return Err(Error::Overflow);
+            return Err(Error::UnexpectedEof {
+                offset,
+                needed: 1,
+                remaining: self.data.len().saturating_sub(offset),
+            });
         }
 
         Reader {
             data: self.data,
             offset,
+            endian: self.endian,
+            max_length: self.max_length,
         }
         .read()
     }
 }
 
-/// A trait representing values that can be read from a `Reader`.
+/// A source of bytes that `Decode` implementations can pull from.
+///
+/// This is implemented by the in-memory `Reader` as well as `StreamReader`, so the same `Decode`
+/// impls work whether the data lives in a slice or is read lazily from a stream.
+pub trait Source {
+    /// Reads bytes to a buffer.
+    fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error>;
+
+    /// Returns the byte order this source decodes multi-byte numbers with.
+    fn endian(&self) -> Endian {
+        Endian::Little
+    }
+
+    /// Returns the maximum number of elements a length-prefixed collection may declare.
+    fn max_length(&self) -> usize {
+        MAX_ARRAY_LENGTH
+    }
+
+    /// Returns the number of bytes still available, or `None` if the source cannot know up front.
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+
+    /// Reads a value implementing `Decode` from this source and advances it.
+    fn read<T>(&mut self) -> Result<T, Error>
+    where
+        T: Decode,
+        Self: Sized,
+    {
+        T::decode(self)
+    }
+}
+
+impl<'a> Source for Reader<'a> {
+    fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        let remaining = self.data.len() - self.offset;
+        if remaining < buffer.len() {
+            return Err(Error::UnexpectedEof {
+                offset: self.offset,
+                needed: buffer.len(),
+                remaining,
+            });
+        }
+
+        buffer.copy_from_slice(&self.data[self.offset..self.offset + buffer.len()]);
+        self.offset += buffer.len();
+
+        Ok(())
+    }
+
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.data.len() - self.offset)
+    }
+}
+
+/// A decoder that pulls bytes from any `std::io::Read` source.
+///
+/// Whereas `Reader` borrows an in-memory slice, `StreamReader` reads lazily from a stream such as a
+/// `TcpStream` or `File`, so the whole payload need not be buffered up front.
+pub struct StreamReader<R> {
+    reader: R,
+    endian: Endian,
+}
+
+impl<R> StreamReader<R>
+where
+    R: Read,
+{
+    /// Constructs a new `StreamReader` wrapping the provided reader, defaulting to `new_le`.
+    pub fn new(reader: R) -> StreamReader<R> {
+        StreamReader::new_le(reader)
+    }
+
+    /// Constructs a new little-endian `StreamReader` wrapping the provided reader.
+    pub fn new_le(reader: R) -> StreamReader<R> {
+        StreamReader {
+            reader,
+            endian: Endian::Little,
+        }
+    }
+
+    /// Constructs a new big-endian `StreamReader` wrapping the provided reader.
+    pub fn new_be(reader: R) -> StreamReader<R> {
+        StreamReader {
+            reader,
+            endian: Endian::Big,
+        }
+    }
+
+    /// Reads `len` bytes from the stream into a freshly allocated buffer.
+    ///
+    /// The length is checked against `MAX_BUF_SIZE` before allocating so a corrupt length field
+    /// cannot trigger an unbounded allocation.
+    pub fn read_vec(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        if len > MAX_BUF_SIZE {
+            return Err(Error::LengthLimitExceeded);
+        }
+
+        let mut buffer = vec![0u8; len];
+        self.read_bytes(&mut buffer)?;
+
+        Ok(buffer)
+    }
+}
+
+impl<R> Source for StreamReader<R>
+where
+    R: Read,
+{
+    fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        self.reader.read_exact(buffer)?;
+
+        Ok(())
+    }
+
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+}
+
+/// A trait representing values that can be read from any `Source`.
 pub trait Decode: Sized {
-    fn decode(reader: &mut Reader) -> Result<Self, Error>;
+    fn decode<S>(source: &mut S) -> Result<Self, Error>
+    where
+        S: Source;
 
     /// Creates a temporary `Reader` and reads itself.
     ///
@@ -63,14 +300,47 @@ pub trait Decode: Sized {
     }
 }
 
+/// A trait representing values that can be borrowed directly out of a `Reader` without copying.
+///
+/// The returned value borrows from the buffer the `Reader` was constructed with, so no allocation
+/// takes place. Owned decoding of the same encodings remains available via `Decode` for `Vec<u8>`
+/// and `String`.
+pub trait DecodeRef<'a>: Sized {
+    fn decode_ref(reader: &mut Reader<'a>) -> Result<Self, Error>;
+}
+
+impl<'a> DecodeRef<'a> for &'a [u8] {
+    fn decode_ref(reader: &mut Reader<'a>) -> Result<&'a [u8], Error> {
+        let len = reader.read::<u32>()? as usize;
+        reader.read_slice(len)
+    }
+}
+
+/// A zero-copy string slice borrowed directly from a `Reader`.
+pub struct Str<'a>(pub &'a str);
+
+impl<'a> DecodeRef<'a> for Str<'a> {
+    fn decode_ref(reader: &mut Reader<'a>) -> Result<Str<'a>, Error> {
+        let bytes = <&[u8]>::decode_ref(reader)?;
+
+        Ok(Str(std::str::from_utf8(bytes)?))
+    }
+}
+
 macro_rules! impl_decode_number {
     ($($ty:ty)*) => {
         $(impl Decode for $ty {
-            fn decode(reader: &mut Reader) -> Result<$ty, Error> {
+            fn decode<S>(source: &mut S) -> Result<$ty, Error>
+            where
+                S: Source,
+            {
                 let mut bytes = [0u8; mem::size_of::<$ty>()];
-                reader.read_bytes(&mut bytes)?;
+                source.read_bytes(&mut bytes)?;
 
-                Ok(<$ty>::from_le_bytes(bytes))
+                Ok(match source.endian() {
+                    Endian::Little => <$ty>::from_le_bytes(bytes),
+                    Endian::Big => <$ty>::from_be_bytes(bytes),
+                })
             }
         })*
     };
@@ -84,10 +354,13 @@ macro_rules! impl_decode_array {
         where
             T: Default + Clone + Copy + Decode
         {
-            fn decode(reader: &mut Reader) -> Result<[T; $length], Error> {
+            fn decode<S>(source: &mut S) -> Result<[T; $length], Error>
+            where
+                S: Source,
+            {
                 let mut data = [Default::default(); $length];
                 for elem in &mut data {
-                    *elem = reader.read()?;
+                    *elem = source.read()?;
                 }
 
                 Ok(data)
@@ -97,3 +370,237 @@ macro_rules! impl_decode_array {
 }
 
 impl_decode_array!(1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+
+/// Validates a declared collection length before allocating for it.
+///
+/// A hostile input must not be trusted to size an allocation, so the length is rejected if it
+/// exceeds the source's configured ceiling or the number of bytes that could possibly remain.
+fn guard_length<S>(source: &S, len: usize) -> Result<(), Error>
+where
+    S: Source,
+{
+    if len > source.max_length() {
+        return Err(Error::LengthLimitExceeded);
+    }
+
+    if let Some(remaining) = source.remaining() {
+        if len > remaining {
+            return Err(Error::LengthLimitExceeded);
+        }
+    }
+
+    Ok(())
+}
+
+impl<T> Decode for Vec<T>
+where
+    T: Decode,
+{
+    fn decode<S>(source: &mut S) -> Result<Vec<T>, Error>
+    where
+        S: Source,
+    {
+        let len = source.read::<u32>()? as usize;
+        guard_length(source, len)?;
+
+        let mut data = Vec::with_capacity(len);
+        for _ in 0..len {
+            data.push(source.read()?);
+        }
+
+        Ok(data)
+    }
+}
+
+impl Decode for String {
+    fn decode<S>(source: &mut S) -> Result<String, Error>
+    where
+        S: Source,
+    {
+        let len = source.read::<u32>()? as usize;
+        guard_length(source, len)?;
+
+        let mut bytes = vec![0u8; len];
+        source.read_bytes(&mut bytes)?;
+
+        String::from_utf8(bytes).map_err(|err| Error::from(err.utf8_error()))
+    }
+}
+
+/// A variable-length integer.
+///
+/// Unsigned types are decoded as LEB128; signed types use the zig-zag mapping on top of it, so small
+/// magnitudes of either sign occupy few bytes.
+pub struct Varint<T>(pub T);
+
+macro_rules! impl_decode_varint_unsigned {
+    ($($ty:ty)*) => {
+        $(impl Decode for Varint<$ty> {
+            fn decode<S>(source: &mut S) -> Result<Varint<$ty>, Error>
+            where
+                S: Source,
+            {
+                let bits = (mem::size_of::<$ty>() * 8) as u32;
+                let mut value: $ty = 0;
+                let mut shift = 0u32;
+                loop {
+                    // A group starting at or past the type width means the encoding is oversized.
+                    if shift >= bits {
+                        return Err(Error::Overflow);
+                    }
+
+                    let byte = source.read::<u8>()?;
+
+                    // On the final group any bit that doesn't fit the remaining width (including a
+                    // stray continuation bit) makes the value too large for the type.
+                    if bits - shift < 7 && byte >> (bits - shift) != 0 {
+                        return Err(Error::Overflow);
+                    }
+
+                    value |= ((byte & 0x7f) as $ty) << shift;
+                    if byte & 0x80 == 0 {
+                        return Ok(Varint(value));
+                    }
+
+                    shift += 7;
+                }
+            }
+        })*
+    };
+}
+
+impl_decode_varint_unsigned!(u16 u32 u64);
+
+macro_rules! impl_decode_varint_signed {
+    ($($signed:ty => $unsigned:ty)*) => {
+        $(impl Decode for Varint<$signed> {
+            fn decode<S>(source: &mut S) -> Result<Varint<$signed>, Error>
+            where
+                S: Source,
+            {
+                let Varint(n) = Varint::<$unsigned>::decode(source)?;
+
+                Ok(Varint(((n >> 1) as $signed) ^ -((n & 1) as $signed)))
+            }
+        })*
+    };
+}
+
+impl_decode_varint_signed!(i32 => u32 i64 => u64);
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, Source, Str, Varint, MAX_ARRAY_LENGTH};
+    use crate::error::Error;
+
+    #[test]
+    fn decode_ref_borrows_buffer() {
+        // u32 length prefix (little-endian) followed by the bytes themselves.
+        let data = [3, 0, 0, 0, b'h', b'i', b'!'];
+
+        let mut reader = Reader::new(&data);
+        let bytes = reader.read_ref::<&[u8]>().unwrap();
+        assert_eq!(bytes, b"hi!");
+        // The returned slice points into the original buffer rather than a copy.
+        assert_eq!(bytes.as_ptr(), data[4..].as_ptr());
+
+        let Str(text) = Reader::new(&data).read_ref::<Str>().unwrap();
+        assert_eq!(text, "hi!");
+        assert_eq!(text.as_ptr(), data[4..].as_ptr());
+    }
+
+    #[test]
+    fn number_respects_byte_order() {
+        let data = [0, 0, 0, 1];
+        assert_eq!(Reader::new_be(&data).read::<u32>().unwrap(), 1);
+        assert_eq!(Reader::new_le(&data).read::<u32>().unwrap(), 0x0100_0000);
+    }
+
+    #[test]
+    fn varint_unsigned_roundtrip() {
+        assert_eq!(Reader::new(&[0x00]).read::<Varint<u16>>().unwrap().0, 0);
+        assert_eq!(Reader::new(&[0x7f]).read::<Varint<u16>>().unwrap().0, 127);
+        assert_eq!(
+            Reader::new(&[0x80, 0x01]).read::<Varint<u16>>().unwrap().0,
+            128
+        );
+        assert_eq!(
+            Reader::new(&[0xff, 0xff, 0x03])
+                .read::<Varint<u16>>()
+                .unwrap()
+                .0,
+            65535
+        );
+    }
+
+    #[test]
+    fn varint_unsigned_overflow() {
+        // High bits of the final group don't fit a u16.
+        assert!(matches!(
+            Reader::new(&[0xff, 0xff, 0x07]).read::<Varint<u16>>(),
+            Err(Error::Overflow)
+        ));
+
+        // Continuation never terminates within the type width.
+        assert!(matches!(
+            Reader::new(&[0x80, 0x80, 0x80]).read::<Varint<u16>>(),
+            Err(Error::Overflow)
+        ));
+    }
+
+    #[test]
+    fn varint_signed_zigzag() {
+        assert_eq!(Reader::new(&[0x00]).read::<Varint<i32>>().unwrap().0, 0);
+        assert_eq!(Reader::new(&[0x01]).read::<Varint<i32>>().unwrap().0, -1);
+        assert_eq!(Reader::new(&[0x02]).read::<Varint<i32>>().unwrap().0, 1);
+        assert_eq!(Reader::new(&[0x03]).read::<Varint<i32>>().unwrap().0, -2);
+    }
+
+    #[test]
+    fn length_limit_rejects_oversized_count() {
+        // A declared count just above MAX_ARRAY_LENGTH must be refused before allocating.
+        let len = (MAX_ARRAY_LENGTH as u32 + 1).to_le_bytes();
+        assert!(matches!(
+            Reader::new(&len).read::<Vec<u8>>(),
+            Err(Error::LengthLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn length_limit_rejects_count_past_remaining() {
+        // Count fits the ceiling but claims more elements than bytes remain.
+        let mut data = 10u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0, 0]);
+        assert!(matches!(
+            Reader::new(&data).read::<Vec<u8>>(),
+            Err(Error::LengthLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn length_limit_honours_custom_max() {
+        let data = 2u32.to_le_bytes();
+        assert!(matches!(
+            Reader::new(&data).with_max_length(1).read::<Vec<u8>>(),
+            Err(Error::LengthLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn read_from_rejects_forward_pointer() {
+        let data = [7u8, 0, 0, 0, 99];
+        let mut reader = Reader::new(&data);
+
+        // Consume the u32, advancing the cursor to offset 4.
+        assert_eq!(reader.read::<u32>().unwrap(), 7);
+
+        // A back-reference before the cursor is fine.
+        assert_eq!(reader.read_from::<u32>(0).unwrap(), 7);
+
+        // One at or past the cursor is a loop and must be rejected.
+        assert!(matches!(
+            reader.read_from::<u8>(4),
+            Err(Error::PointerOverlap)
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::str::Utf8Error;
+
+/// An error that can occur while decoding.
+#[derive(Debug)]
+pub enum Error {
+    /// A variable-length integer was too large for its target type or used too many bytes.
+    Overflow,
+    /// A read ran past the end of the buffer, with the position and sizes that caused it.
+    UnexpectedEof {
+        /// Offset at which the read was attempted.
+        offset: usize,
+        /// Number of bytes requested.
+        needed: usize,
+        /// Number of bytes actually remaining from `offset`.
+        remaining: usize,
+    },
+    /// Decoded string bytes did not contain valid UTF-8.
+    Utf8(Utf8Error),
+    /// An underlying stream returned an I/O error.
+    Io(io::Error),
+    /// A back-reference pointed at or past the current position, risking a decode loop.
+    PointerOverlap,
+    /// A length-prefixed collection declared more elements than are permitted or available.
+    LengthLimitExceeded,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::Overflow => write!(f, "variable-length integer overflows target type"),
+            Error::UnexpectedEof {
+                offset,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "unexpected end of input at offset {}: needed {} bytes, {} remaining",
+                offset, needed, remaining
+            ),
+            Error::Utf8(err) => write!(f, "invalid UTF-8: {}", err),
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::PointerOverlap => write!(f, "back-reference points at or past current position"),
+            Error::LengthLimitExceeded => write!(f, "declared collection length exceeds limit"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Utf8(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Utf8Error> for Error {
+    fn from(err: Utf8Error) -> Error {
+        Error::Utf8(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}